@@ -1,15 +1,28 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use byteorder::{LittleEndian, ReadBytesExt};
+use solana_program::program::{invoke, invoke_signed};
 use solana_program::program_error::ProgramError;
 use solana_program::sysvar::clock::Clock;
+use solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
 use solana_program::sysvar::Sysvar;
 use solana_program::{
     account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+    system_instruction,
 };
 
 // Kritik işlev için varsayılan gecikme süresi
 const DEFAULT_DELAY_FOR_CRITICAL_FUNCTION: i64 = 30;
 
+// Vault PDA'sının türetildiği seed
+const VAULT_SEED: &[u8] = b"vault";
+
+// Bir CheckExecution ile aynı atomik işlemde bulunması yasak olan talimat etiketleri:
+// QueueCriticalFunction, CancelFunction ve SetDelegate. Bunlara izin verilirse bir saldırgan
+// tek bir atomik işlemde kuyruğa alma/onaylama/yürütme veya delege değişimini zamanlayabilir.
+const DISALLOWED_CO_INSTRUCTION_TAGS: [u8; 3] = [0, 1, 3];
+
 // Giriş noktası makrosu
 entrypoint!(process_instruction);
 
@@ -18,6 +31,10 @@ entrypoint!(process_instruction);
 pub struct ContractState {
     pub queued_functions: Vec<QueuedFunction>,
     pub delegate: Option<Pubkey>,
+    // Minimum gecikmeleri yapılandırma yetkisine sahip hesap
+    pub authority: Pubkey,
+    // Her CriticalFunction türü için yönetişim tarafından belirlenen taban gecikme
+    pub min_delays: Vec<(FunctionKind, i64)>,
 }
 
 // Kuyrukta bekleyen işlev veri yapısı
@@ -28,6 +45,40 @@ pub struct QueuedFunction {
     pub cancelled: bool,
     pub initiator: Pubkey,
     pub delegate: Option<Pubkey>,
+    // Kuyruğa alma sırasında belirlenen onay eşiği ve onay verebilecek imzacılar
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    // Zaman içinde toplanan onaylar
+    pub approvals: Vec<Pubkey>,
+}
+
+// Sözleşmenin yürütebileceği kritik işlevler
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum CriticalFunction {
+    WithdrawAllFunds {
+        amount: u64,
+        target_pubkey: Pubkey,
+    },
+    DeleteAccount {
+        target_pubkey: Pubkey,
+    },
+}
+
+impl CriticalFunction {
+    // Minimum gecikme yapılandırmasını aramak için kullanılan tür etiketi
+    fn kind(&self) -> FunctionKind {
+        match self {
+            CriticalFunction::WithdrawAllFunds { .. } => FunctionKind::WithdrawAllFunds,
+            CriticalFunction::DeleteAccount { .. } => FunctionKind::DeleteAccount,
+        }
+    }
+}
+
+// CriticalFunction varyantlarının veri taşımayan türü; min_delays içinde anahtar olarak kullanılır
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub enum FunctionKind {
+    WithdrawAllFunds,
+    DeleteAccount,
 }
 
 // Talimat veri türü
@@ -36,14 +87,30 @@ pub enum Instruction {
     QueueCriticalFunction {
         function: CriticalFunction,
         delay_in_seconds: i64,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
     },
     CancelFunction {
         function_index: usize,
     },
-    CheckExecution,
+    CheckExecution {
+        function_index: usize,
+    },
     SetDelegate {
         delegate_pubkey: Pubkey,
     },
+    ApproveFunction {
+        function_index: usize,
+    },
+    UpdateFunction {
+        function_index: usize,
+        new_function: Option<CriticalFunction>,
+        new_delay_in_seconds: Option<i64>,
+    },
+    SetMinDelay {
+        function_kind: FunctionKind,
+        seconds: i64,
+    },
 }
 
 impl Instruction {
@@ -56,13 +123,26 @@ impl Instruction {
         Ok(match tag {
             0 => {
                 let function = CriticalFunction::try_from_slice(&rest)?;
-                let delay_in_seconds = rest[rest.len() - 8..]
-                    .as_ref()
+                let mut offset = function.try_to_vec()?.len();
+                let delay_in_seconds = (&rest[offset..offset + 8])
                     .read_i64::<LittleEndian>()
                     .map_err(|_| ProgramError::InvalidInstructionData)?;
+                offset += 8;
+                let approver_count = (&rest[offset..offset + 4])
+                    .read_u32::<LittleEndian>()
+                    .map_err(|_| ProgramError::InvalidInstructionData)? as usize;
+                offset += 4;
+                let mut approvers = Vec::with_capacity(approver_count);
+                for _ in 0..approver_count {
+                    approvers.push(Pubkey::new(&rest[offset..offset + 32]));
+                    offset += 32;
+                }
+                let threshold = rest[offset];
                 Self::QueueCriticalFunction {
                     function,
                     delay_in_seconds,
+                    approvers,
+                    threshold,
                 }
             }
             1 => {
@@ -73,11 +153,73 @@ impl Instruction {
                     as usize;
                 Self::CancelFunction { function_index }
             }
-            2 => Self::CheckExecution,
+            2 => {
+                let function_index = rest
+                    .as_ref()
+                    .read_u64::<LittleEndian>()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?
+                    as usize;
+                Self::CheckExecution { function_index }
+            }
             3 => {
                 let delegate_pubkey = Pubkey::new(&rest[0..32]);
                 Self::SetDelegate { delegate_pubkey }
             }
+            4 => {
+                let function_index = rest
+                    .as_ref()
+                    .read_u64::<LittleEndian>()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?
+                    as usize;
+                Self::ApproveFunction { function_index }
+            }
+            5 => {
+                let mut offset = 0;
+                let function_index = (&rest[offset..offset + 8])
+                    .read_u64::<LittleEndian>()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?
+                    as usize;
+                offset += 8;
+                let has_new_function = rest[offset];
+                offset += 1;
+                let new_function = if has_new_function == 1 {
+                    let function = CriticalFunction::try_from_slice(&rest[offset..])?;
+                    offset += function.try_to_vec()?.len();
+                    Some(function)
+                } else {
+                    None
+                };
+                let has_new_delay = rest[offset];
+                offset += 1;
+                let new_delay_in_seconds = if has_new_delay == 1 {
+                    Some(
+                        (&rest[offset..offset + 8])
+                            .read_i64::<LittleEndian>()
+                            .map_err(|_| ProgramError::InvalidInstructionData)?,
+                    )
+                } else {
+                    None
+                };
+                Self::UpdateFunction {
+                    function_index,
+                    new_function,
+                    new_delay_in_seconds,
+                }
+            }
+            6 => {
+                let function_kind = match rest[0] {
+                    0 => FunctionKind::WithdrawAllFunds,
+                    1 => FunctionKind::DeleteAccount,
+                    _ => return Err(ProgramError::InvalidInstructionData),
+                };
+                let seconds = (&rest[1..9])
+                    .read_i64::<LittleEndian>()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                Self::SetMinDelay {
+                    function_kind,
+                    seconds,
+                }
+            }
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
@@ -88,19 +230,43 @@ pub fn queue_function(
     accounts: &[AccountInfo],
     function: CriticalFunction,
     delay_in_seconds: i64,
+    approvers: Vec<Pubkey>,
+    threshold: u8,
 ) -> ProgramResult {
     let account = &accounts[0];
+    let caller = &accounts[1];
     let mut state: ContractState = if account.data_len() > 0 {
         ContractState::try_from_slice(&account.data.borrow())?
     } else {
         ContractState {
             queued_functions: Vec::new(),
             delegate: None,
+            authority: *caller.key,
+            min_delays: Vec::new(),
         }
     };
-    let clock = Clock::from_account_info(&accounts[1])?;
+
+    // Sadece yetki sahibi (authority) veya delege yeni bir kritik işlev kuyruğa alabilir;
+    // aksi halde kasayı tamamen boşaltacak bir WithdrawAllFunds herkes tarafından kuyruğa alınabilirdi.
+    if !caller.is_signer {
+        msg!("Caller must sign to queue a critical function");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *caller.key != state.authority && Some(*caller.key) != state.delegate {
+        msg!("Only the authority or delegate may queue a critical function");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let clock = Clock::from_account_info(&accounts[2])?;
     let current_time = clock.unix_timestamp;
-    let execution_time = current_time + delay_in_seconds;
+    let configured_min = state
+        .min_delays
+        .iter()
+        .find(|(kind, _)| *kind == function.kind())
+        .map(|(_, seconds)| *seconds)
+        .unwrap_or(DEFAULT_DELAY_FOR_CRITICAL_FUNCTION);
+    let actual_delay = delay_in_seconds.max(configured_min);
+    let execution_time = current_time + actual_delay;
     msg!(
         "Function queued: {:?}, Execution time: {}",
         function,
@@ -110,8 +276,11 @@ pub fn queue_function(
         function,
         execution_time,
         cancelled: false,
-        initiator: *account.key,
+        initiator: *caller.key,
         delegate: None,
+        approvers,
+        threshold,
+        approvals: Vec::new(),
     };
 
     state.queued_functions.push(queued_function);
@@ -120,9 +289,145 @@ pub fn queue_function(
     Ok(())
 }
 
+// Kuyruktaki bir işlevi yürütmek için geçirilen hesapların, işlevin ihtiyaç
+// duyduğu yazılabilirlik/imzalayıcı bayraklarıyla eşleştiğini doğrular, böylece
+// bozuk bir CheckExecution çağrısı salt okunur bir hesabı mutasyona uğratamaz.
+fn validate_execution_accounts(
+    program_id: &Pubkey,
+    function: &CriticalFunction,
+    vault_account: &AccountInfo,
+    target_account: &AccountInfo,
+) -> ProgramResult {
+    match function {
+        CriticalFunction::WithdrawAllFunds { target_pubkey, .. } => {
+            if target_account.key != target_pubkey {
+                msg!("Target account does not match the queued target pubkey");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if !vault_account.is_writable || !target_account.is_writable {
+                msg!("Vault and target accounts must both be writable for a withdrawal");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            // PDA olmayan bir vault, transfer için kendisi imzalamış olmalıdır
+            let (vault_pda, _) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
+            if vault_account.key != &vault_pda && !vault_account.is_signer {
+                msg!("Vault account must sign a withdrawal unless it is the vault PDA");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        }
+        CriticalFunction::DeleteAccount { target_pubkey } => {
+            if vault_account.key != target_pubkey {
+                msg!("Vault account does not match the account queued for deletion");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if !vault_account.is_writable || !target_account.is_writable {
+                msg!("Vault and destination accounts must both be writable for a deletion");
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Bir CriticalFunction'ı gerçek bir durum değişikliğine dönüştürür: para transferleri
+// System Program'a CPI ile, hesap silme ise lamport'ları boşaltıp veri uzunluğunu
+// sıfırlayarak runtime'ın hesabı geri kazanmasını sağlayarak yapılır.
+fn execute_function(
+    program_id: &Pubkey,
+    function: &CriticalFunction,
+    system_program: &AccountInfo,
+    vault_account: &AccountInfo,
+    target_account: &AccountInfo,
+) -> ProgramResult {
+    validate_execution_accounts(program_id, function, vault_account, target_account)?;
+
+    match function {
+        CriticalFunction::WithdrawAllFunds {
+            amount,
+            target_pubkey,
+        } => {
+            msg!(
+                "Transferring {} lamports from vault to {}",
+                amount,
+                target_pubkey
+            );
+            let transfer_ix = system_instruction::transfer(vault_account.key, target_pubkey, *amount);
+            let (vault_pda, vault_bump) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
+            let account_infos = &[
+                vault_account.clone(),
+                target_account.clone(),
+                system_program.clone(),
+            ];
+            if vault_account.key == &vault_pda {
+                invoke_signed(&transfer_ix, account_infos, &[&[VAULT_SEED, &[vault_bump]]])?;
+            } else {
+                invoke(&transfer_ix, account_infos)?;
+            }
+        }
+        CriticalFunction::DeleteAccount { target_pubkey } => {
+            msg!("Deleting account {}", target_pubkey);
+            let dest_starting_lamports = target_account.lamports();
+            **target_account.lamports.borrow_mut() = dest_starting_lamports
+                .checked_add(vault_account.lamports())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            **vault_account.lamports.borrow_mut() = 0;
+            vault_account.realloc(0, false)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Aynı işlemdeki diğer talimatları (program_id, etiket, hedeflenen durum hesabı) üçlüleri
+// olarak toplar; hedef hesap, her talimatın ilk hesabı (ContractState) olarak kabul edilir
+fn scan_co_instructions(
+    instructions_sysvar: &AccountInfo,
+    current_index: u16,
+) -> Result<Vec<(Pubkey, u8, Option<Pubkey>)>, ProgramError> {
+    let mut discovered = Vec::new();
+    let mut index: u16 = 0;
+    while let Ok(ix) = load_instruction_at_checked(index as usize, instructions_sysvar) {
+        if index != current_index {
+            if let Some(&tag) = ix.data.first() {
+                let target_state_account = ix.accounts.first().map(|meta| meta.pubkey);
+                discovered.push((ix.program_id, tag, target_state_account));
+            }
+        }
+        index += 1;
+    }
+    Ok(discovered)
+}
+
+// Instructions sysvar'ını okuyarak aynı işlemde, bu programa ait AYNI ContractState hesabını
+// hedefleyen tehlikeli bir co-instruction bulunmadığını doğrular (atomik bypass koruması).
+// Hedef hesabı karşılaştırmak, farklı bir ContractState'e (örn. başka bir kiracı/vault'a) yönelik
+// meşru, eşzamanlı talimatların yanlışlıkla reddedilmesini önler.
+fn assert_no_disallowed_co_instructions(
+    instructions_sysvar: &AccountInfo,
+    program_id: &Pubkey,
+    state_account: &Pubkey,
+) -> ProgramResult {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    for (co_program_id, tag, target_state_account) in
+        scan_co_instructions(instructions_sysvar, current_index)?
+    {
+        if co_program_id == *program_id
+            && target_state_account == Some(*state_account)
+            && DISALLOWED_CO_INSTRUCTION_TAGS.contains(&tag)
+        {
+            msg!(
+                "Rejecting CheckExecution: disallowed co-instruction tag {} targeting this state account in the same transaction",
+                tag
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    }
+    Ok(())
+}
+
 // Talimatların işlenmesi
 fn process_instruction(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
@@ -132,79 +437,195 @@ fn process_instruction(
     match instruction {
         Instruction::QueueCriticalFunction {
             function,
-            delay_in_seconds: _,
+            delay_in_seconds,
+            approvers,
+            threshold,
         } => {
-            let actual_delay = match &function {
-                CriticalFunction::WithdrawAllFunds { .. }
-                | CriticalFunction::DeleteAccount { .. } => DEFAULT_DELAY_FOR_CRITICAL_FUNCTION,
-            };
-            queue_function(accounts, function, actual_delay)?;
+            // queue_function, gerçek gecikmeyi isteğin kendisi ile ContractState
+            // içindeki yönetişim tarafından belirlenen taban arasından en büyüğünü seçer
+            queue_function(accounts, function, delay_in_seconds, approvers, threshold)?;
         }
         Instruction::CancelFunction { function_index } => {
             msg!("Received instruction to cancel function at index: {}", function_index);
             let account = &accounts[0];
+            let caller = &accounts[1];
             let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
             if function_index < state.queued_functions.len() {
                 let queued_func = &mut state.queued_functions[function_index];
-                if *account.key != queued_func.initiator
-                    && Some(*account.key) != queued_func.delegate
+                if !caller.is_signer {
+                    msg!("Caller must sign to cancel a queued function");
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if *caller.key != queued_func.initiator
+                    && Some(*caller.key) != queued_func.delegate
                 {
                     msg!("Invalid instruction data: {:?}", instruction_data);
                     return Err(ProgramError::InvalidAccountData);
                 }
                 queued_func.cancelled = true;
+                // İptal edilen bir işlev yeniden kuyruğa alınmadan onaylar geçerliliğini korumamalı
+                queued_func.approvals.clear();
                 state.serialize(&mut &mut account.data.borrow_mut()[..])?;
             } else {
                 msg!("Invalid instruction data: {:?}", function_index);
                 return Err(ProgramError::InvalidInstructionData);
             }
         }
+        Instruction::ApproveFunction { function_index } => {
+            let account = &accounts[0];
+            let caller = &accounts[1];
+            let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
+            if function_index >= state.queued_functions.len() {
+                msg!("Invalid instruction data: {:?}", function_index);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            if !caller.is_signer {
+                msg!("Caller must sign to approve a queued function");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let queued_func = &mut state.queued_functions[function_index];
+            if !queued_func.approvers.contains(caller.key) {
+                msg!("Account is not an approver for this function: {:?}", caller.key);
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if !queued_func.approvals.contains(caller.key) {
+                queued_func.approvals.push(*caller.key);
+            }
+            state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+        }
+        Instruction::UpdateFunction {
+            function_index,
+            new_function,
+            new_delay_in_seconds,
+        } => {
+            let account = &accounts[0];
+            let caller = &accounts[1];
+            let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
+            if function_index >= state.queued_functions.len() {
+                msg!("Invalid instruction data: {:?}", function_index);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let clock = Clock::from_account_info(&accounts[2])?;
+            if !caller.is_signer {
+                msg!("Caller must sign to update a queued function");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let queued_func = &mut state.queued_functions[function_index];
+            if *caller.key != queued_func.initiator && Some(*caller.key) != queued_func.delegate
+            {
+                msg!("Invalid instruction data: {:?}", instruction_data);
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if queued_func.cancelled {
+                msg!("Cannot update a cancelled function");
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            let effective_kind = new_function
+                .as_ref()
+                .map(|f| f.kind())
+                .unwrap_or_else(|| queued_func.function.kind());
+            let configured_min = state
+                .min_delays
+                .iter()
+                .find(|(kind, _)| *kind == effective_kind)
+                .map(|(_, seconds)| *seconds)
+                .unwrap_or(DEFAULT_DELAY_FOR_CRITICAL_FUNCTION);
+
+            let queued_func = &mut state.queued_functions[function_index];
+            if let Some(function) = new_function {
+                queued_func.function = function;
+                // İşlev değiştiğinde eski onaylar yeni işlev için geçerli sayılamaz
+                queued_func.approvals.clear();
+            }
+            if let Some(new_delay_in_seconds) = new_delay_in_seconds {
+                // Gecikmeyi yeniden başlatır; yönetişimin belirlediği tabanın altına asla düşürmez
+                let actual_delay = new_delay_in_seconds.max(configured_min);
+                queued_func.execution_time = clock.unix_timestamp + actual_delay;
+            }
+            state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+        }
+        Instruction::SetMinDelay {
+            function_kind,
+            seconds,
+        } => {
+            let account = &accounts[0];
+            // Yetki sahibi, durum hesabının kendi anahtarı değil ayrı bir imzalayıcı hesaptır;
+            // aksi halde bu karşılaştırma her zaman kendisiyle eşleşir ve hiçbir koruma sağlamaz.
+            let authority_candidate = &accounts[1];
+            let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
+            if !authority_candidate.is_signer {
+                msg!("Caller must sign to set a minimum delay");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *authority_candidate.key != state.authority {
+                msg!("Only the authority can set minimum delays");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if let Some(entry) = state
+                .min_delays
+                .iter_mut()
+                .find(|(kind, _)| *kind == function_kind)
+            {
+                entry.1 = seconds;
+            } else {
+                state.min_delays.push((function_kind, seconds));
+            }
+            state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+        }
         Instruction::SetDelegate { delegate_pubkey } => {
             let account = &accounts[0];
+            // Yetki sahibi, durum hesabının kendi anahtarı değil ayrı bir imzalayıcı hesaptır
+            let caller = &accounts[1];
             let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
+            if !caller.is_signer {
+                msg!("Caller must sign to set the delegate");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *caller.key != state.authority {
+                msg!("Only the authority can set the delegate");
+                return Err(ProgramError::InvalidAccountData);
+            }
             state.delegate = Some(delegate_pubkey);
             state.serialize(&mut &mut account.data.borrow_mut()[..])?;
         }
-        Instruction::CheckExecution => {
+        Instruction::CheckExecution { function_index } => {
             let account = &accounts[0];
             let mut state: ContractState = ContractState::try_from_slice(&account.data.borrow())?;
             let clock = Clock::from_account_info(&accounts[1])?;
             let current_time = clock.unix_timestamp;
+            let system_program = &accounts[2];
+            let vault_account = &accounts[3];
+            let target_account = &accounts[4];
+            let instructions_sysvar = &accounts[5];
 
-            let mut functions_to_remove = Vec::new();
-
-            for (index, func) in state.queued_functions.iter_mut().enumerate() {
-                msg!("Checking function at index: {}: {:?}", index, func);
-                if !func.cancelled && func.execution_time <= current_time {
-                    match &func.function {
-                        CriticalFunction::WithdrawAllFunds {
-                            amount,
-                            target_pubkey,
-                        } => {
-                            msg!(
-                                "Executing Withdraw Funds function. Amount: {}. Target Pubkey: {}",
-                                amount,
-                                target_pubkey
-                            );
-                            msg!(
-                                "Sending {} lamports for transfer to {}",
-                                amount,
-                                target_pubkey
-                            );
-                            functions_to_remove.push(index);
-                        }
-                        CriticalFunction::DeleteAccount { .. } => {
-                            msg!("Executing Delete Account function..");
-                            functions_to_remove.push(index);
-                        }
-                    }
-                }
+            assert_no_disallowed_co_instructions(instructions_sysvar, program_id, account.key)?;
+
+            if function_index >= state.queued_functions.len() {
+                msg!("Invalid instruction data: {:?}", function_index);
+                return Err(ProgramError::InvalidInstructionData);
             }
 
-            for index in functions_to_remove.iter().rev() {
-                state.queued_functions.remove(*index);
+            // Her çağrı tek bir kuyruklanmış işlevi hedefler: kendi vault/target hesap çiftiyle
+            // birlikte gelir, böylece farklı hedefleri olan iki uygun işlev birbirinin
+            // yürütülmesini engelleyemez (biri diğerinin hesaplarıyla doğrulanıp geri alınamaz).
+            let func = &state.queued_functions[function_index];
+            msg!("Checking function at index: {}: {:?}", function_index, func);
+            let is_approved = func.approvals.len() as u8 >= func.threshold;
+            if func.cancelled || func.execution_time > current_time || !is_approved {
+                msg!("Function at index {} is not yet eligible for execution", function_index);
+                return Err(ProgramError::InvalidArgument);
             }
 
+            execute_function(
+                program_id,
+                &func.function,
+                system_program,
+                vault_account,
+                target_account,
+            )?;
+
+            state.queued_functions.remove(function_index);
             state.serialize(&mut &mut account.data.borrow_mut()[..])?;
         }
     }